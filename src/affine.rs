@@ -5,6 +5,8 @@ use crate::definitions::{Clamp, HasBlack, Image};
 use crate::math::cast;
 use conv::ValueInto;
 use std::ops::Mul;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
 /// A 2d affine transform, stored as a row major 3x3 matrix.
 #[derive(Copy, Clone, Debug)]
@@ -18,56 +20,201 @@ impl Affine2 {
     pub fn from_matrix_unchecked(transform: [f32; 9]) -> Affine2 {
         Affine2 { transform }
     }
+
+    /// Computes the affine transform mapping each of the three `src` points to the
+    /// corresponding `dst` point. Returns `None` if the source points are collinear,
+    /// as the resulting system of equations is then singular.
+    pub fn from_control_points(src: [(f32, f32); 3], dst: [(f32, f32); 3]) -> Option<Affine2> {
+        let mut rows = Vec::with_capacity(6);
+        for ((x, y), (xp, yp)) in src.iter().zip(dst.iter()) {
+            rows.push((vec![*x, *y, 1.0, 0.0, 0.0, 0.0], *xp));
+            rows.push((vec![0.0, 0.0, 0.0, *x, *y, 1.0], *yp));
+        }
+
+        let h = solve_linear_system(rows)?;
+
+        Some(Affine2::from_matrix_unchecked([
+            h[0], h[1], h[2],
+            h[3], h[4], h[5],
+            0.0, 0.0, 1.0,
+        ]))
+    }
+
+    /// The identity transform.
+    pub fn identity() -> Affine2 {
+        Affine2::from_matrix_unchecked([
+            1.0, 0.0, 0.0,
+            0.0, 1.0, 0.0,
+            0.0, 0.0, 1.0,
+        ])
+    }
+
+    /// A transform that translates by `(tx, ty)`.
+    pub fn translate(tx: f32, ty: f32) -> Affine2 {
+        Affine2::from_matrix_unchecked([
+            1.0, 0.0, tx,
+            0.0, 1.0, ty,
+            0.0, 0.0, 1.0,
+        ])
+    }
+
+    /// A transform that scales the x and y axes by `sx` and `sy` respectively,
+    /// about the origin.
+    pub fn scale(sx: f32, sy: f32) -> Affine2 {
+        Affine2::from_matrix_unchecked([
+            sx, 0.0, 0.0,
+            0.0, sy, 0.0,
+            0.0, 0.0, 1.0,
+        ])
+    }
+
+    /// A transform that rotates clockwise about the origin by `theta` radians.
+    pub fn rotate(theta: f32) -> Affine2 {
+        let cos_theta = theta.cos();
+        let sin_theta = theta.sin();
+        Affine2::from_matrix_unchecked([
+            cos_theta, -sin_theta, 0.0,
+            sin_theta, cos_theta, 0.0,
+            0.0, 0.0, 1.0,
+        ])
+    }
+
+    /// A transform that shears the x and y axes by `kx` and `ky` respectively.
+    pub fn shear(kx: f32, ky: f32) -> Affine2 {
+        Affine2::from_matrix_unchecked([
+            1.0, kx, 0.0,
+            ky, 1.0, 0.0,
+            0.0, 0.0, 1.0,
+        ])
+    }
+}
+
+impl Mul<Affine2> for Affine2 {
+    type Output = Affine2;
+
+    /// Composes two affine transforms, with `self` applied after `rhs`.
+    fn mul(self, rhs: Affine2) -> Affine2 {
+        let a = &self.transform;
+        let b = &rhs.transform;
+
+        let mut t = [0f32; 9];
+        for row in 0..3 {
+            for col in 0..3 {
+                t[row * 3 + col] = (0..3).map(|k| a[row * 3 + k] * b[k * 3 + col]).sum();
+            }
+        }
+
+        Affine2::from_matrix_unchecked(t)
+    }
 }
 
 impl Affine2 {
     fn try_inverse(&self) -> Option<Self> {
+        invert_3x3(&self.transform).map(Self::from_matrix_unchecked)
+    }
+}
+
+impl Mul<Point2> for Affine2 {
+    type Output = Point2;
+
+    fn mul(self, rhs: Point2) -> Point2 {
         let t = &self.transform;
-        let (
-            t00, t01, t02,
-            t10, t11, t12,
-            t20, t21, t22
-        ) = (
-            t[0], t[1], t[2],
-            t[3], t[4], t[5],
-            t[6], t[7], t[8]
-        );
+        Point2 {
+            x: t[0] * rhs.x + t[1] * rhs.y + t[2],
+            y: t[3] * rhs.x + t[4] * rhs.y + t[5]
+        }
+    }
+}
 
-        let m00 = t11 * t22 - t12 * t21;
-        let m01 = t10 * t22 - t12 * t20;
-        let m02 = t10 * t21 - t11 * t20;
+/// A 2d projective transform (homography), stored as a row major 3x3 matrix.
+/// Unlike [`Affine2`], the bottom row is not assumed to be `[0, 0, 1]`, so
+/// this can represent perspective effects such as keystone correction that
+/// an affine transform cannot.
+#[derive(Copy, Clone, Debug)]
+pub struct Projection {
+    transform: [f32; 9]
+}
 
-        let det = t00 * m00 - t01 * m01 + t02 * m02;
+impl Projection {
+    /// Create a 2d projective transform from a row-major 3x3 matrix in
+    /// homogeneous coordinates. The provided matrix is not checked to be
+    /// invertible.
+    pub fn from_matrix_unchecked(transform: [f32; 9]) -> Projection {
+        Projection { transform }
+    }
 
-        if det == 0.0 {
-            return None;
+    /// Computes the homography mapping each of the four `src` points to the
+    /// corresponding `dst` point, via the direct linear transform. Returns
+    /// `None` if the resulting system of equations is singular, e.g. when the
+    /// source points all lie on one line.
+    pub fn from_control_points(src: [(f32, f32); 4], dst: [(f32, f32); 4]) -> Option<Projection> {
+        let mut rows = Vec::with_capacity(8);
+        for ((x, y), (xp, yp)) in src.iter().zip(dst.iter()) {
+            rows.push((vec![*x, *y, 1.0, 0.0, 0.0, 0.0, -x * xp, -y * xp], *xp));
+            rows.push((vec![0.0, 0.0, 0.0, *x, *y, 1.0, -x * yp, -y * yp], *yp));
         }
 
-        let m10 = t01 * t22 - t02 * t21;
-        let m11 = t00 * t22 - t02 * t20;
-        let m12 = t00 * t21 - t01 * t20;
-        let m20 = t01 * t12 - t02 * t11;
-        let m21 = t00 * t12 - t02 * t10;
-        let m22 = t00 * t11 - t01 * t10;
+        let h = solve_linear_system(rows)?;
 
-        let inv = [
-             m00 / det, -m10 / det,  m20 / det,
-            -m01 / det,  m11 / det, -m21 / det,
-             m02 / det, -m12 / det,  m22 / det
-        ];
+        Some(Projection::from_matrix_unchecked([
+            h[0], h[1], h[2],
+            h[3], h[4], h[5],
+            h[6], h[7], 1.0,
+        ]))
+    }
 
-        Some(Self::from_matrix_unchecked(inv))
+    fn try_inverse(&self) -> Option<Self> {
+        invert_3x3(&self.transform).map(Self::from_matrix_unchecked)
     }
 }
 
-impl Mul<Point2> for Affine2 {
+/// Inverts a row-major 3x3 matrix via the cofactor/adjugate method, or returns
+/// `None` if it is singular. Shared by [`Affine2::try_inverse`] and
+/// [`Projection::try_inverse`].
+fn invert_3x3(t: &[f32; 9]) -> Option<[f32; 9]> {
+    let (
+        t00, t01, t02,
+        t10, t11, t12,
+        t20, t21, t22
+    ) = (
+        t[0], t[1], t[2],
+        t[3], t[4], t[5],
+        t[6], t[7], t[8]
+    );
+
+    let m00 = t11 * t22 - t12 * t21;
+    let m01 = t10 * t22 - t12 * t20;
+    let m02 = t10 * t21 - t11 * t20;
+
+    let det = t00 * m00 - t01 * m01 + t02 * m02;
+
+    if det == 0.0 {
+        return None;
+    }
+
+    let m10 = t01 * t22 - t02 * t21;
+    let m11 = t00 * t22 - t02 * t20;
+    let m12 = t00 * t21 - t01 * t20;
+    let m20 = t01 * t12 - t02 * t11;
+    let m21 = t00 * t12 - t02 * t10;
+    let m22 = t00 * t11 - t01 * t10;
+
+    Some([
+         m00 / det, -m10 / det,  m20 / det,
+        -m01 / det,  m11 / det, -m21 / det,
+         m02 / det, -m12 / det,  m22 / det
+    ])
+}
+
+impl Mul<Point2> for Projection {
     type Output = Point2;
 
     fn mul(self, rhs: Point2) -> Point2 {
         let t = &self.transform;
+        let w = t[6] * rhs.x + t[7] * rhs.y + t[8];
         Point2 {
-            x: t[0] * rhs.x + t[1] * rhs.y + t[2],
-            y: t[3] * rhs.x + t[4] * rhs.y + t[5]
+            x: (t[0] * rhs.x + t[1] * rhs.y + t[2]) / w,
+            y: (t[3] * rhs.x + t[4] * rhs.y + t[5]) / w
         }
     }
 }
@@ -94,6 +241,83 @@ pub enum Interpolation {
     /// Bilinearly interpolate between the four pixels
     /// closest to the pre-image of the output pixel.
     Bilinear,
+    /// Interpolate between the sixteen pixels closest to the
+    /// pre-image of the output pixel using a Catmull-Rom cubic
+    /// convolution kernel.
+    Bicubic,
+    /// Interpolate between the thirty-six pixels closest to the
+    /// pre-image of the output pixel using a windowed-sinc
+    /// Lanczos kernel with a support of 3 pixels.
+    Lanczos3,
+}
+
+/// How to handle output pixels whose pre-image lies outside the input image.
+#[derive(Copy, Clone, Debug)]
+pub enum EdgeMode<P> {
+    /// Use the given pixel value for all out-of-bounds pre-images.
+    Constant(P),
+    /// Saturate out-of-bounds coordinates to the nearest edge pixel.
+    Clamp,
+    /// Mirror out-of-bounds coordinates at the image boundary.
+    Reflect,
+    /// Wrap out-of-bounds coordinates around to the opposite edge, modulo
+    /// the image dimensions.
+    Wrap,
+}
+
+impl<P: Copy> EdgeMode<P> {
+    /// Maps a possibly out-of-bounds `(x, y)` pre-image coordinate to an
+    /// in-bounds pixel coordinate, or `None` if there is no such mapping
+    /// (which can only happen for `EdgeMode::Constant`, or for any edge mode
+    /// when `width` or `height` is zero).
+    fn resolve(&self, x: i32, y: i32, width: u32, height: u32) -> Option<(u32, u32)> {
+        match (resolve_axis(self, x, width), resolve_axis(self, y, height)) {
+            (Some(rx), Some(ry)) => Some((rx, ry)),
+            _ => None,
+        }
+    }
+
+    /// The pixel to use when `resolve` returns `None`.
+    ///
+    /// For `Clamp`/`Reflect`/`Wrap`, `resolve` only returns `None` when
+    /// sampling a zero-sized image axis, in which case there is no pixel to
+    /// fall back to and callers must not reach this point; every sampler in
+    /// this module is only ever invoked with a non-empty source image.
+    fn fallback(&self) -> P {
+        match self {
+            EdgeMode::Constant(p) => *p,
+            EdgeMode::Clamp | EdgeMode::Reflect | EdgeMode::Wrap => {
+                unreachable!("samplers are never invoked against a zero-sized image")
+            }
+        }
+    }
+}
+
+fn resolve_axis<P>(edge_mode: &EdgeMode<P>, i: i32, size: u32) -> Option<u32> {
+    if size == 0 {
+        return None;
+    }
+    let size = size as i32;
+
+    match edge_mode {
+        EdgeMode::Constant(_) => {
+            if i >= 0 && i < size {
+                Some(i as u32)
+            } else {
+                None
+            }
+        }
+        EdgeMode::Clamp => Some(i.max(0).min(size - 1) as u32),
+        EdgeMode::Wrap => Some((((i % size) + size) % size) as u32),
+        EdgeMode::Reflect => {
+            if size == 1 {
+                return Some(0);
+            }
+            let period = 2 * (size - 1);
+            let m = ((i % period) + period) % period;
+            Some((if m < size { m } else { period - m }) as u32)
+        }
+    }
 }
 
 /// Applies an affine transformation to an image, or None if the provided
@@ -106,25 +330,26 @@ pub fn affine<P>(
     interpolation: Interpolation,
 ) -> Option<Image<P>>
 where
-    P: Pixel + HasBlack + 'static,
-    <P as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32>,
+    P: Pixel + HasBlack + Send + Sync + 'static,
+    <P as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32> + Send + Sync,
 {
-    affine_with_default(image, affine, P::black(), interpolation)
+    affine_with_default(image, affine, EdgeMode::Constant(P::black()), interpolation)
 }
 
 /// Applies an affine transformation to an image, or None if the provided
 /// transformation is not invertible.
 /// The output image has the same dimensions as the input. Output pixels
-/// whose pre-image lies outside the input image are set to default.
+/// whose pre-image lies outside the input image are handled according to
+/// `edge_mode`.
 pub fn affine_with_default<P>(
     image: &Image<P>,
     affine: Affine2,
-    default: P,
+    edge_mode: EdgeMode<P>,
     interpolation: Interpolation,
 ) -> Option<Image<P>>
 where
-    P: Pixel + 'static,
-    <P as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32>,
+    P: Pixel + Send + Sync + 'static,
+    <P as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32> + Send + Sync,
 {
     let inverse: Affine2;
     match affine.try_inverse() {
@@ -135,6 +360,102 @@ where
     let (width, height) = image.dimensions();
     let mut out = ImageBuffer::new(width, height);
 
+    #[cfg(feature = "rayon")]
+    {
+        let compute_row = |y: u32| -> Vec<P> {
+            (0..width)
+                .map(|x| {
+                    let preimage = inverse * Point2::new(x as f32, y as f32);
+                    let px = preimage.x;
+                    let py = preimage.y;
+
+                    match interpolation {
+                        Interpolation::Nearest => nearest(image, px, py, edge_mode),
+                        Interpolation::Bilinear => interpolate(image, px, py, edge_mode),
+                        Interpolation::Bicubic => interpolate_bicubic(image, px, py, edge_mode),
+                        Interpolation::Lanczos3 => interpolate_lanczos3(image, px, py, edge_mode),
+                    }
+                })
+                .collect()
+        };
+
+        let rows: Vec<Vec<P>> = (0..height).into_par_iter().map(compute_row).collect();
+        for (y, row) in rows.into_iter().enumerate() {
+            for (x, pix) in row.into_iter().enumerate() {
+                unsafe {
+                    out.unsafe_put_pixel(x as u32, y as u32, pix);
+                }
+            }
+        }
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    {
+        for y in 0..height {
+            for x in 0..width {
+                let preimage = inverse * Point2::new(x as f32, y as f32);
+                let px = preimage.x;
+                let py = preimage.y;
+
+                let pix = match interpolation {
+                    Interpolation::Nearest => nearest(image, px, py, edge_mode),
+                    Interpolation::Bilinear => interpolate(image, px, py, edge_mode),
+                    Interpolation::Bicubic => interpolate_bicubic(image, px, py, edge_mode),
+                    Interpolation::Lanczos3 => interpolate_lanczos3(image, px, py, edge_mode),
+                };
+                unsafe {
+                    out.unsafe_put_pixel(x, y, pix);
+                }
+            }
+        }
+    }
+
+    Some(out)
+}
+
+/// Applies a projective transformation to an image, or None if the provided
+/// transformation is not invertible.
+/// The output image has the same dimensions as the input. Output pixels
+/// whose pre-image lies outside the input image are set to black.
+pub fn warp<P>(
+    image: &Image<P>,
+    projection: Projection,
+    interpolation: Interpolation,
+) -> Option<Image<P>>
+where
+    P: Pixel + HasBlack + 'static,
+    <P as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32>,
+{
+    warp_with_default(image, projection, EdgeMode::Constant(P::black()), interpolation)
+}
+
+/// Applies a projective transformation to an image, or None if the provided
+/// transformation is not invertible.
+/// The output image has the same dimensions as the input. Output pixels
+/// whose pre-image lies outside the input image are handled according to
+/// `edge_mode`.
+///
+/// This can be used for perspective rectification, e.g. straightening a
+/// photographed document or game screenshot that was captured at an angle.
+pub fn warp_with_default<P>(
+    image: &Image<P>,
+    projection: Projection,
+    edge_mode: EdgeMode<P>,
+    interpolation: Interpolation,
+) -> Option<Image<P>>
+where
+    P: Pixel + 'static,
+    <P as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32>,
+{
+    let inverse: Projection;
+    match projection.try_inverse() {
+        None => return None,
+        Some(inv) => inverse = inv,
+    }
+
+    let (width, height) = image.dimensions();
+    let mut out = ImageBuffer::new(width, height);
+
     for y in 0..height {
         for x in 0..width {
             let preimage = inverse * Point2::new(x as f32, y as f32);
@@ -142,8 +463,10 @@ where
             let py = preimage.y;
 
             let pix = match interpolation {
-                Interpolation::Nearest => nearest(image, px, py, default),
-                Interpolation::Bilinear => interpolate(image, px, py, default),
+                Interpolation::Nearest => nearest(image, px, py, edge_mode),
+                Interpolation::Bilinear => interpolate(image, px, py, edge_mode),
+                Interpolation::Bicubic => interpolate_bicubic(image, px, py, edge_mode),
+                Interpolation::Lanczos3 => interpolate_lanczos3(image, px, py, edge_mode),
             };
             unsafe {
                 out.unsafe_put_pixel(x, y, pix);
@@ -164,14 +487,14 @@ pub fn rotate<P>(
     interpolation: Interpolation,
 ) -> Image<P>
 where
-    P: Pixel + HasBlack + 'static,
-    <P as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32>,
+    P: Pixel + HasBlack + Send + Sync + 'static,
+    <P as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32> + Send + Sync,
 {
     rotate_with_default(
         image,
         center,
         theta,
-        <P as HasBlack>::black(),
+        EdgeMode::Constant(<P as HasBlack>::black()),
         interpolation,
     )
 }
@@ -185,8 +508,8 @@ pub fn rotate_about_center<P>(
     interpolation: Interpolation,
 ) -> Image<P>
 where
-    P: Pixel + HasBlack + 'static,
-    <P as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32>,
+    P: Pixel + HasBlack + Send + Sync + 'static,
+    <P as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32> + Send + Sync,
 {
     let center = (image.width() as f32 / 2f32, image.height() as f32 / 2f32);
     rotate(image, center, theta, interpolation)
@@ -194,132 +517,199 @@ where
 
 /// Rotate an image clockwise about provided center by theta radians.
 /// The output image has the same dimensions as the input. Output pixels
-/// whose pre-image lies outside the input image are set to default.
+/// whose pre-image lies outside the input image are handled according to
+/// `edge_mode`.
 pub fn rotate_with_default<P>(
     image: &Image<P>,
     center: (f32, f32),
     theta: f32,
-    default: P,
+    edge_mode: EdgeMode<P>,
     interpolation: Interpolation,
 ) -> Image<P>
 where
-    P: Pixel + 'static,
-    <P as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32>,
+    P: Pixel + Send + Sync + 'static,
+    <P as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32> + Send + Sync,
 {
-    match interpolation {
-        Interpolation::Nearest => rotate_nearest(image, center, theta, default),
-        Interpolation::Bilinear => rotate_bilinear(image, center, theta, default),
-    }
+    let (cx, cy) = center;
+    let transform = Affine2::translate(cx, cy) * Affine2::rotate(theta) * Affine2::translate(-cx, -cy);
+
+    // A rotation about a point is always invertible.
+    affine_with_default(image, transform, edge_mode, interpolation)
+        .expect("rotations are always invertible")
 }
 
-fn rotate_nearest<P>(image: &Image<P>, center: (f32, f32), theta: f32, default: P) -> Image<P>
+/// Translates the input image by t. Note that image coordinates increase from
+/// top left to bottom right. Output pixels whose pre-image are not in the input
+/// image are set to the boundary pixel in the input image nearest to their pre-image.
+pub fn translate<P>(image: &Image<P>, t: (i32, i32)) -> Image<P>
 where
-    P: Pixel + 'static,
+    P: Pixel + Send + Sync + 'static,
+    <P as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32> + Send + Sync,
 {
-    let (width, height) = image.dimensions();
-    let mut out = ImageBuffer::new(width, height);
+    let transform = Affine2::translate(t.0 as f32, t.1 as f32);
 
-    let cos_theta = theta.cos();
-    let sin_theta = theta.sin();
-    let center_x = center.0;
-    let center_y = center.1;
+    // A translation is always invertible.
+    affine_with_default(image, transform, EdgeMode::Clamp, Interpolation::Nearest)
+        .expect("translations are always invertible")
+}
 
-    for y in 0..height {
-        let dy = y as f32 - center_y;
-        let mut px = center_x + sin_theta * dy - cos_theta * center_x;
-        let mut py = center_y + cos_theta * dy + sin_theta * center_x;
+/// Solves the linear system described by `rows`, each a pair of coefficients
+/// and a right hand side value, via Gaussian elimination with partial
+/// pivoting. Returns `None` if the system is singular.
+fn solve_linear_system(mut rows: Vec<(Vec<f32>, f32)>) -> Option<Vec<f32>> {
+    let n = rows.len();
 
-        for x in 0..width {
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| rows[i].0[col].abs().partial_cmp(&rows[j].0[col].abs()).unwrap())
+            .unwrap();
 
-            unsafe {
-                let pix = nearest(image, px, py, default);
-                out.unsafe_put_pixel(x, y, pix);
-            }
+        if rows[pivot].0[col].abs() < 1e-10 {
+            return None;
+        }
+
+        rows.swap(col, pivot);
+
+        let (pivot_coeffs, pivot_rhs) = rows[col].clone();
+        let pivot_value = pivot_coeffs[col];
 
-            px += cos_theta;
-            py -= sin_theta;
+        for row in col + 1..n {
+            let factor = rows[row].0[col] / pivot_value;
+            if factor == 0.0 {
+                continue;
+            }
+            for k in col..n {
+                rows[row].0[k] -= factor * pivot_coeffs[k];
+            }
+            rows[row].1 -= factor * pivot_rhs;
         }
     }
 
-    out
+    let mut solution = vec![0f32; n];
+    for row in (0..n).rev() {
+        let (coeffs, rhs) = &rows[row];
+        let sum: f32 = (row + 1..n).map(|k| coeffs[k] * solution[k]).sum();
+        solution[row] = (rhs - sum) / coeffs[row];
+    }
+
+    Some(solution)
 }
 
-fn rotate_bilinear<P>(image: &Image<P>, center: (f32, f32), theta: f32, default: P) -> Image<P>
+/// Resizes an image to the given dimensions, independent of its original
+/// aspect ratio. Each output pixel `(x, y)` is mapped back to the source
+/// coordinate `((x + 0.5) * width / new_width - 0.5, (y + 0.5) * height / new_height - 0.5)`
+/// and sampled using the given interpolation. Pixels whose pre-image lies
+/// outside the input image are clamped to the nearest edge pixel.
+///
+/// When shrinking an image by a large factor, point-sampling the pre-image
+/// aliases badly, so this instead averages over the footprint each output
+/// pixel covers in the source image.
+pub fn resize<P>(
+    image: &Image<P>,
+    new_width: u32,
+    new_height: u32,
+    interpolation: Interpolation,
+) -> Image<P>
 where
     P: Pixel + 'static,
     <P as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32>,
 {
     let (width, height) = image.dimensions();
-    let mut out = ImageBuffer::new(width, height);
 
-    let cos_theta = theta.cos();
-    let sin_theta = theta.sin();
-    let center_x = center.0;
-    let center_y = center.1;
+    // The samplers below assume a non-empty source image: unlike `affine`/`warp`,
+    // where the output always shares the input's (possibly zero) dimensions and
+    // so never actually samples, `resize` can be asked for a non-empty output
+    // from an empty source, so this has to be handled explicitly.
+    if width == 0 || height == 0 {
+        return ImageBuffer::new(new_width, new_height);
+    }
 
-    for y in 0..height {
-        let dy = y as f32 - center_y;
-        let mut px = center_x + sin_theta * dy - cos_theta * center_x;
-        let mut py = center_y + cos_theta * dy + sin_theta * center_x;
+    // This dispatch is per-image, not per-axis: an image shrunk by more than
+    // 2x on one axis but enlarged on the other still goes through `resize_box`
+    // in full, so that axis's alignment won't match a same-ratio resize done
+    // through the point-sample loop below. See the note on `resize_box`.
+    if width > 2 * new_width.max(1) || height > 2 * new_height.max(1) {
+        return resize_box(image, new_width, new_height);
+    }
 
-        for x in 0..width {
+    // Samples can fall slightly outside the image near its edges; clamp
+    // rather than fading to black, since that's not aliasing we want to hide.
+    let edge_mode = EdgeMode::Clamp;
+    let mut out = ImageBuffer::new(new_width, new_height);
+
+    for y in 0..new_height {
+        let py = (y as f32 + 0.5) * height as f32 / new_height as f32 - 0.5;
 
-            let pix = interpolate(image, px, py, default);
+        for x in 0..new_width {
+            let px = (x as f32 + 0.5) * width as f32 / new_width as f32 - 0.5;
+
+            let pix = match interpolation {
+                Interpolation::Nearest => nearest(image, px, py, edge_mode),
+                Interpolation::Bilinear => interpolate(image, px, py, edge_mode),
+                Interpolation::Bicubic => interpolate_bicubic(image, px, py, edge_mode),
+                Interpolation::Lanczos3 => interpolate_lanczos3(image, px, py, edge_mode),
+            };
             unsafe {
                 out.unsafe_put_pixel(x, y, pix);
             }
-
-            px += cos_theta;
-            py -= sin_theta;
         }
     }
 
     out
 }
 
-/// Translates the input image by t. Note that image coordinates increase from
-/// top left to bottom right. Output pixels whose pre-image are not in the input
-/// image are set to the boundary pixel in the input image nearest to their pre-image.
-// TODO: it's possibly confusing that this has different behaviour to
-// TODO: attempting the equivalent transformation via the affine function
-pub fn translate<P>(image: &Image<P>, t: (i32, i32)) -> Image<P>
+/// Resizes an image by averaging, for each output pixel, the source pixels
+/// whose footprint it covers. Used by [`resize`] when downscaling by a large
+/// factor, where point-sampling the pre-image would alias badly.
+///
+/// Each footprint spans `floor(x * scale)..ceil((x + 1) * scale)`, which is
+/// not centered the way the `(x + 0.5) * scale - 0.5` sampling point used by
+/// `resize`'s point-sample loop is. This only matters on an axis that's
+/// not itself shrunk by more than 2x, since `resize` dispatches
+/// per-image rather than per-axis: such an axis is averaged here with a half-
+/// pixel alignment shift relative to what a same-ratio single-axis resize
+/// would produce through the point-sample loop.
+fn resize_box<P>(image: &Image<P>, new_width: u32, new_height: u32) -> Image<P>
 where
     P: Pixel + 'static,
+    <P as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32>,
 {
-    use std::cmp;
-
     let (width, height) = image.dimensions();
-    let mut out = ImageBuffer::new(width, height);
-
-    let w = width as i32;
-    let h = height as i32;
-
-    for y in 0..height {
-        let y_in = cmp::max(0, cmp::min(y as i32 - t.1, h - 1));
-
-        if t.0 > 0 {
-            let p_min = *image.get_pixel(0, y_in as u32);
-            for x in 0..(t.0).min(w) {
-                out.put_pixel(x as u32, y, p_min);
+    let scale_x = width as f32 / new_width as f32;
+    let scale_y = height as f32 / new_height as f32;
+
+    let mut out = ImageBuffer::new(new_width, new_height);
+
+    for y in 0..new_height {
+        let src_y0 = (y as f32 * scale_y).floor() as u32;
+        let src_y1 = (((y + 1) as f32 * scale_y).ceil() as u32)
+            .max(src_y0 + 1)
+            .min(height);
+
+        for x in 0..new_width {
+            let src_x0 = (x as f32 * scale_x).floor() as u32;
+            let src_x1 = (((x + 1) as f32 * scale_x).ceil() as u32)
+                .max(src_x0 + 1)
+                .min(width);
+
+            let mut acc = vec![0f32; P::CHANNEL_COUNT as usize];
+            let mut count = 0f32;
+            for sy in src_y0..src_y1 {
+                for sx in src_x0..src_x1 {
+                    let p = unsafe { image.unsafe_get_pixel(sx, sy) };
+                    for (a, c) in acc.iter_mut().zip(p.channels()) {
+                        *a += cast(*c);
+                    }
+                    count += 1f32;
+                }
             }
 
-            if t.0 < w {
-                let in_base = (y_in as usize * width as usize) * P::CHANNEL_COUNT as usize;
-                let out_base = (y as usize * width as usize + (t.0 as usize)) * P::CHANNEL_COUNT as usize;
-                let len = (w - t.0) as usize * P::CHANNEL_COUNT as usize;
-                (*out)[out_base..][..len].copy_from_slice(&(**image)[in_base..][..len]);
-            }
-        } else {
-            let p_max = *image.get_pixel(width - 1, y_in as u32);
-            for x in (w + t.0).max(0)..w {
-                out.put_pixel(x as u32, y, p_max);
+            let mut out_pixel = unsafe { image.unsafe_get_pixel(src_x0, src_y0) };
+            for (c, a) in out_pixel.channels_mut().iter_mut().zip(acc) {
+                *c = P::Subpixel::clamp(a / count);
             }
-
-            if w + t.0 > 0 {
-                let in_base = (y_in as usize * width as usize - (t.0 as usize)) * P::CHANNEL_COUNT as usize;
-                let out_base = (y as usize * width as usize) * P::CHANNEL_COUNT as usize;
-                let len = (w + t.0) as usize * P::CHANNEL_COUNT as usize;
-                (*out)[out_base..][..len].copy_from_slice(&(**image)[in_base..][..len]);
+            unsafe {
+                out.unsafe_put_pixel(x, y, out_pixel);
             }
         }
     }
@@ -352,7 +742,7 @@ where
     })
 }
 
-fn interpolate<P>(image: &Image<P>, x: f32, y: f32, default: P) -> P
+fn interpolate<P>(image: &Image<P>, x: f32, y: f32, edge_mode: EdgeMode<P>) -> P
 where
     P: Pixel + 'static,
     <P as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32>,
@@ -365,34 +755,172 @@ where
     let right_weight = x - left;
     let bottom_weight = y - top;
 
-    // default if out of bound
     let (width, height) = image.dimensions();
-    if left < 0f32 || right >= width as f32 || top < 0f32 || bottom >= height as f32 {
-        default
-    } else {
+    let taps = (
+        edge_mode.resolve(left as i32, top as i32, width, height),
+        edge_mode.resolve(right as i32, top as i32, width, height),
+        edge_mode.resolve(left as i32, bottom as i32, width, height),
+        edge_mode.resolve(right as i32, bottom as i32, width, height),
+    );
+
+    if let (Some((lx, ty)), Some((rx, ty2)), Some((lx2, by)), Some((rx2, by2))) = taps {
         let (tl, tr, bl, br) = unsafe {
             (
-                image.unsafe_get_pixel(left as u32, top as u32),
-                image.unsafe_get_pixel(right as u32, top as u32),
-                image.unsafe_get_pixel(left as u32, bottom as u32),
-                image.unsafe_get_pixel(right as u32, bottom as u32),
+                image.unsafe_get_pixel(lx, ty),
+                image.unsafe_get_pixel(rx, ty2),
+                image.unsafe_get_pixel(lx2, by),
+                image.unsafe_get_pixel(rx2, by2),
             )
         };
         blend(tl, tr, bl, br, right_weight, bottom_weight)
+    } else {
+        edge_mode.fallback()
     }
 }
 
-fn nearest<P: Pixel + 'static>(image: &Image<P>, x: f32, y: f32, default: P) -> P {
-    let rx = x.round();
-    let ry = y.round();
-
-    // default if out of bound
+fn nearest<P: Pixel + 'static>(image: &Image<P>, x: f32, y: f32, edge_mode: EdgeMode<P>) -> P {
     let (width, height) = image.dimensions();
-    if rx < 0f32 || rx >= width as f32 || ry < 0f32 || ry >= height as f32 {
-        default
+
+    match edge_mode.resolve(x.round() as i32, y.round() as i32, width, height) {
+        Some((rx, ry)) => unsafe { image.unsafe_get_pixel(rx, ry) },
+        None => edge_mode.fallback(),
+    }
+}
+
+/// Catmull-Rom cubic convolution kernel with `a = -0.5`, as used by
+/// e.g. Adobe Photoshop and Pillow's `BICUBIC` resampling filter.
+fn cubic_kernel(t: f32) -> f32 {
+    let a = -0.5f32;
+    let t = t.abs();
+
+    if t <= 1f32 {
+        (a + 2f32) * t * t * t - (a + 3f32) * t * t + 1f32
+    } else if t < 2f32 {
+        a * t * t * t - 5f32 * a * t * t + 8f32 * a * t - 4f32 * a
     } else {
-        unsafe { image.unsafe_get_pixel(rx as u32, ry as u32) }
+        0f32
+    }
+}
+
+/// Windowed-sinc Lanczos kernel with a support of 3 pixels.
+fn lanczos3_kernel(x: f32) -> f32 {
+    fn sinc(x: f32) -> f32 {
+        if x == 0f32 {
+            1f32
+        } else {
+            let pix = std::f32::consts::PI * x;
+            pix.sin() / pix
+        }
+    }
+
+    if x.abs() < 3f32 {
+        sinc(x) * sinc(x / 3f32)
+    } else {
+        0f32
+    }
+}
+
+fn interpolate_bicubic<P>(image: &Image<P>, x: f32, y: f32, edge_mode: EdgeMode<P>) -> P
+where
+    P: Pixel + 'static,
+    <P as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32>,
+{
+    let left = x.floor();
+    let top = y.floor();
+    let tx = x - left;
+    let ty = y - top;
+
+    let weights_x = [
+        cubic_kernel(tx + 1f32),
+        cubic_kernel(tx),
+        cubic_kernel(tx - 1f32),
+        cubic_kernel(tx - 2f32),
+    ];
+    let weights_y = [
+        cubic_kernel(ty + 1f32),
+        cubic_kernel(ty),
+        cubic_kernel(ty - 1f32),
+        cubic_kernel(ty - 2f32),
+    ];
+
+    convolve_taps(image, left as i32 - 1, top as i32 - 1, &weights_x, &weights_y, edge_mode)
+}
+
+fn interpolate_lanczos3<P>(image: &Image<P>, x: f32, y: f32, edge_mode: EdgeMode<P>) -> P
+where
+    P: Pixel + 'static,
+    <P as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32>,
+{
+    let left = x.floor();
+    let top = y.floor();
+    let tx = x - left;
+    let ty = y - top;
+
+    let weights_x = [
+        lanczos3_kernel(tx + 2f32),
+        lanczos3_kernel(tx + 1f32),
+        lanczos3_kernel(tx),
+        lanczos3_kernel(tx - 1f32),
+        lanczos3_kernel(tx - 2f32),
+        lanczos3_kernel(tx - 3f32),
+    ];
+    let weights_y = [
+        lanczos3_kernel(ty + 2f32),
+        lanczos3_kernel(ty + 1f32),
+        lanczos3_kernel(ty),
+        lanczos3_kernel(ty - 1f32),
+        lanczos3_kernel(ty - 2f32),
+        lanczos3_kernel(ty - 3f32),
+    ];
+
+    convolve_taps(image, left as i32 - 2, top as i32 - 2, &weights_x, &weights_y, edge_mode)
+}
+
+/// Accumulates `sum(w_x[i] * w_y[j] * pixel)` over the `N x N` neighborhood of
+/// `(origin_x, origin_y)`, normalizing by the sum of weights and clamping each
+/// channel back into range. Taps whose coordinates lie outside the image are
+/// remapped according to `edge_mode`; under `EdgeMode::Constant`, a single
+/// such tap makes the whole result the constant pixel.
+fn convolve_taps<P>(
+    image: &Image<P>,
+    origin_x: i32,
+    origin_y: i32,
+    weights_x: &[f32],
+    weights_y: &[f32],
+    edge_mode: EdgeMode<P>,
+) -> P
+where
+    P: Pixel + 'static,
+    <P as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32>,
+{
+    let n = weights_x.len();
+    let (width, height) = image.dimensions();
+    // The accumulation below sums `weights_x[i] * weights_y[j]` over every
+    // `(i, j)` pair, so the normalizing sum must match that full cross product
+    // rather than just the element-wise (same-index) pairing.
+    let weight_sum: f32 = weights_x.iter().sum::<f32>() * weights_y.iter().sum::<f32>();
+
+    let mut acc = vec![0f32; P::CHANNEL_COUNT as usize];
+
+    for j in 0..n {
+        for i in 0..n {
+            let weight = weights_x[i] * weights_y[j];
+            let (tx, ty) = match edge_mode.resolve(origin_x + i as i32, origin_y + j as i32, width, height) {
+                Some(coords) => coords,
+                None => return edge_mode.fallback(),
+            };
+            let pixel = unsafe { image.unsafe_get_pixel(tx, ty) };
+            for (a, c) in acc.iter_mut().zip(pixel.channels()) {
+                *a += weight * cast(*c);
+            }
+        }
+    }
+
+    let mut out = unsafe { image.unsafe_get_pixel(0, 0) };
+    for (c, a) in out.channels_mut().iter_mut().zip(acc) {
+        *c = P::Subpixel::clamp(a / weight_sum);
     }
+    out
 }
 
 #[cfg(test)]
@@ -408,7 +936,8 @@ mod tests {
             00, 01, 02;
             10, 11, 12);
 
-        let rotated = rotate_nearest(&image, (1f32, 0f32), 0f32, Luma([99u8]));
+        let rotated = rotate_with_default(
+            &image, (1f32, 0f32), 0f32, EdgeMode::Constant(Luma([99u8])), Interpolation::Nearest);
         assert_pixels_eq!(rotated, image);
     }
 
@@ -424,7 +953,8 @@ mod tests {
             11, 01, 99;
             12, 02, 99);
 
-        let rotated = rotate_nearest(&image, (1f32, 0f32), f32::consts::PI / 2f32, Luma([99u8]));
+        let rotated = rotate_with_default(
+            &image, (1f32, 0f32), f32::consts::PI / 2f32, EdgeMode::Constant(Luma([99u8])), Interpolation::Nearest);
         assert_pixels_eq!(rotated, expected);
     }
 
@@ -440,7 +970,8 @@ mod tests {
             12, 11, 10;
             02, 01, 00);
 
-        let rotated = rotate_nearest(&image, (1f32, 0.5f32), -f32::consts::PI, Luma([99u8]));
+        let rotated = rotate_with_default(
+            &image, (1f32, 0.5f32), -f32::consts::PI, EdgeMode::Constant(Luma([99u8])), Interpolation::Nearest);
         assert_pixels_eq!(rotated, expected);
     }
 
@@ -448,7 +979,8 @@ mod tests {
     fn bench_rotate_nearest(b: &mut test::Bencher) {
         let image = GrayImage::from_pixel(200, 200, Luma([15u8]));
         b.iter(|| {
-            let rotated = rotate_nearest(&image, (3f32, 3f32), 1f32, Luma([0u8]));
+            let rotated = rotate_with_default(
+                &image, (3f32, 3f32), 1f32, EdgeMode::Constant(Luma([0u8])), Interpolation::Nearest);
             test::black_box(rotated);
         });
     }
@@ -457,11 +989,99 @@ mod tests {
     fn bench_rotate_bilinear(b: &mut test::Bencher) {
         let image = GrayImage::from_pixel(200, 200, Luma([15u8]));
         b.iter(|| {
-            let rotated = rotate_bilinear(&image, (3f32, 3f32), 1f32, Luma([0u8]));
+            let rotated = rotate_with_default(
+                &image, (3f32, 3f32), 1f32, EdgeMode::Constant(Luma([0u8])), Interpolation::Bilinear);
             test::black_box(rotated);
         });
     }
 
+    #[test]
+    fn test_cubic_kernel() {
+        assert!((cubic_kernel(0f32) - 1f32).abs() < 1e-6);
+        assert!((cubic_kernel(1f32) - 0f32).abs() < 1e-6);
+        assert!((cubic_kernel(2f32) - 0f32).abs() < 1e-6);
+        assert!((cubic_kernel(-1f32) - 0f32).abs() < 1e-6);
+        assert!((cubic_kernel(0.5f32) - 0.5625f32).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_lanczos3_kernel() {
+        assert!((lanczos3_kernel(0f32) - 1f32).abs() < 1e-6);
+        assert!((lanczos3_kernel(1f32) - 0f32).abs() < 1e-6);
+        assert!((lanczos3_kernel(2f32) - 0f32).abs() < 1e-6);
+        assert!((lanczos3_kernel(-1f32) - 0f32).abs() < 1e-6);
+        assert!((lanczos3_kernel(3f32) - 0f32).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_interpolate_bicubic_at_integer_coords() {
+        let image = gray_image!(
+            00, 01, 02, 03, 04;
+            10, 11, 12, 13, 14;
+            20, 21, 22, 23, 24;
+            30, 31, 32, 33, 34;
+            40, 41, 42, 43, 44);
+
+        let sampled = interpolate_bicubic(&image, 2f32, 2f32, EdgeMode::Clamp);
+        assert_pixels_eq!(GrayImage::from_pixel(1, 1, sampled), GrayImage::from_pixel(1, 1, Luma([22u8])));
+    }
+
+    #[test]
+    fn test_interpolate_lanczos3_at_integer_coords() {
+        let image = gray_image!(
+            00, 01, 02, 03, 04, 05, 06;
+            10, 11, 12, 13, 14, 15, 16;
+            20, 21, 22, 23, 24, 25, 26;
+            30, 31, 32, 33, 34, 35, 36;
+            40, 41, 42, 43, 44, 45, 46;
+            50, 51, 52, 53, 54, 55, 56;
+            60, 61, 62, 63, 64, 65, 66);
+
+        let sampled = interpolate_lanczos3(&image, 3f32, 3f32, EdgeMode::Clamp);
+        assert_pixels_eq!(GrayImage::from_pixel(1, 1, sampled), GrayImage::from_pixel(1, 1, Luma([33u8])));
+    }
+
+    // The two tests above only sample at exact integer coordinates, where all
+    // but one kernel tap is zero, so they never touch the blending math. These
+    // sample at fractional coordinates instead, with expected values worked
+    // out by hand from `cubic_kernel`/`lanczos3_kernel`.
+    #[test]
+    fn test_interpolate_bicubic_at_fractional_coords() {
+        let image = gray_image!(
+            00, 01, 02, 03, 04;
+            10, 11, 12, 13, 14;
+            20, 21, 22, 23, 24;
+            30, 31, 32, 33, 34;
+            40, 41, 42, 43, 44);
+
+        // x = 2.25, y = 2.25 blends all 16 taps around (2, 2); working out
+        // cubic_kernel's weights by hand for tx = ty = 0.25 and summing
+        // weight * pixel over the 4x4 neighbourhood gives 24.75, which
+        // rounds to 25.
+        let sampled = interpolate_bicubic(&image, 2.25f32, 2.25f32, EdgeMode::Clamp);
+        assert_pixels_eq!(GrayImage::from_pixel(1, 1, sampled), GrayImage::from_pixel(1, 1, Luma([25u8])));
+    }
+
+    #[test]
+    fn test_interpolate_lanczos3_at_fractional_coords() {
+        let image = gray_image!(
+            00, 01, 02, 03, 04, 05, 06;
+            10, 11, 12, 13, 14, 15, 16;
+            20, 21, 22, 23, 24, 25, 26;
+            30, 31, 32, 33, 34, 35, 36;
+            40, 41, 42, 43, 44, 45, 46;
+            50, 51, 52, 53, 54, 55, 56;
+            60, 61, 62, 63, 64, 65, 66);
+
+        // x = 3.25, y = 3 (an integer) blends all 6 horizontal taps around
+        // row 3 but leaves the vertical axis on a single tap, so the
+        // expected value can be worked out from lanczos3_kernel's 1D
+        // weights alone: summing weight * pixel over row 3's 6-pixel
+        // neighbourhood and normalizing gives ~33.23, which rounds to 33.
+        let sampled = interpolate_lanczos3(&image, 3.25f32, 3f32, EdgeMode::Clamp);
+        assert_pixels_eq!(GrayImage::from_pixel(1, 1, sampled), GrayImage::from_pixel(1, 1, Luma([33u8])));
+    }
+
     #[test]
     fn test_translate_positive_x_positive_y() {
         let image = gray_image!(
@@ -545,6 +1165,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_affine_with_edge_mode_wrap() {
+        let image = gray_image!(
+            00, 01, 02;
+            10, 11, 12;
+            20, 21, 22);
+
+        let expected = gray_image!(
+            22, 20, 21;
+            02, 00, 01;
+            12, 10, 11);
+
+        let aff = Affine2::from_matrix_unchecked([
+            1.0, 0.0, 1.0,
+            0.0, 1.0, 1.0,
+            0.0, 0.0, 1.0,
+        ]);
+
+        let translated = affine_with_default(&image, aff, EdgeMode::Wrap, Interpolation::Nearest)
+            .expect("Affine transformation returned None");
+        assert_pixels_eq!(translated, expected);
+    }
+
+    #[test]
+    fn test_affine_with_edge_mode_reflect() {
+        let image = gray_image!(
+            00, 01, 02;
+            10, 11, 12;
+            20, 21, 22);
+
+        let expected = gray_image!(
+            11, 10, 11;
+            01, 00, 01;
+            11, 10, 11);
+
+        let aff = Affine2::from_matrix_unchecked([
+            1.0, 0.0, 1.0,
+            0.0, 1.0, 1.0,
+            0.0, 0.0, 1.0,
+        ]);
+
+        let translated = affine_with_default(&image, aff, EdgeMode::Reflect, Interpolation::Nearest)
+            .expect("Affine transformation returned None");
+        assert_pixels_eq!(translated, expected);
+    }
+
     #[bench]
     fn bench_affine_nearest(b: &mut test::Bencher) {
         let image = GrayImage::from_pixel(200, 200, Luma([15u8]));
@@ -576,4 +1242,190 @@ mod tests {
             test::black_box(transformed);
         });
     }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_affine_with_default_rayon_matches_serial_result() {
+        // Exercises the `rayon`-backed row-parallel path specifically; the
+        // expected output is identical to the serial path's, since rows are
+        // independent and the two code paths only differ in how they're
+        // scheduled.
+        let image = gray_image!(
+            00, 01, 02;
+            10, 11, 12;
+            20, 21, 22);
+
+        let expected = gray_image!(
+            00, 00, 00;
+            00, 00, 01;
+            00, 10, 11);
+
+        let aff = Affine2::from_matrix_unchecked([
+            1.0, 0.0, 1.0,
+            0.0, 1.0, 1.0,
+            0.0, 0.0, 1.0,
+        ]);
+
+        let translated = affine_with_default(&image, aff, EdgeMode::Constant(Luma([0u8])), Interpolation::Nearest)
+            .expect("Affine transformation returned None");
+        assert_pixels_eq!(translated, expected);
+    }
+
+    #[test]
+    fn test_resize_box_average() {
+        // Downscaling by more than 2x routes through `resize_box`, which
+        // should average the footprint of source pixels covered by each
+        // output pixel rather than point-sampling.
+        let image = gray_image!(10, 20, 30, 40);
+
+        let resized = resize(&image, 1, 1, Interpolation::Bilinear);
+        assert_pixels_eq!(resized, gray_image!(25));
+    }
+
+    #[test]
+    fn test_resize_zero_sized_source() {
+        let image = GrayImage::new(0, 3);
+        let resized = resize(&image, 2, 2, Interpolation::Nearest);
+        assert_eq!(resized.dimensions(), (2, 2));
+    }
+
+    #[test]
+    fn test_resize_nearest_upsize_uses_point_sample_loop() {
+        // width (2) is not more than 2 * new_width (4), so this goes through
+        // the point-sample main loop rather than `resize_box`, with each
+        // source pixel's pre-image covering a 2x2 block of output pixels.
+        let image = gray_image!(
+            0, 1;
+            2, 3);
+
+        let resized = resize(&image, 4, 4, Interpolation::Nearest);
+        assert_pixels_eq!(
+            resized,
+            gray_image!(
+                0, 0, 1, 1;
+                0, 0, 1, 1;
+                2, 2, 3, 3;
+                2, 2, 3, 3)
+        );
+    }
+
+    #[test]
+    fn test_resize_bilinear_downsize_below_2x_uses_point_sample_loop() {
+        // width (4) is not more than 2 * new_width (3), so this goes through
+        // the point-sample main loop rather than `resize_box`. Expected
+        // values are `(x + 0.5) * 4 / 3 - 0.5` sampled into [10, 20, 30, 40]
+        // and linearly interpolated, rounded to the nearest integer.
+        let image = gray_image!(10, 20, 30, 40);
+
+        let resized = resize(&image, 3, 1, Interpolation::Bilinear);
+        assert_pixels_eq!(resized, gray_image!(12, 25, 38));
+    }
+
+    #[test]
+    fn test_resize_bicubic_downsize_below_2x_uses_point_sample_loop() {
+        // width (6) is not more than 2 * new_width (5), so this goes
+        // through the point-sample main loop with `interpolate_bicubic`.
+        // Expected values are hand-computed from `cubic_kernel`'s weights
+        // at each `(x + 0.5) * 6 / 5 - 0.5` sample point, clamping taps
+        // that fall outside the source to its edge pixels.
+        let image = gray_image!(10, 20, 30, 40, 50, 60);
+
+        let resized = resize(&image, 5, 1, Interpolation::Bicubic);
+        assert_pixels_eq!(resized, gray_image!(11, 23, 35, 47, 59));
+    }
+
+    #[test]
+    fn test_warp() {
+        let image = gray_image!(
+            00, 01, 02;
+            10, 11, 12;
+            20, 21, 22);
+
+        let expected = gray_image!(
+            00, 00, 00;
+            00, 00, 01;
+            00, 10, 11);
+
+        let projection = Projection::from_matrix_unchecked([
+            1.0, 0.0, 1.0,
+            0.0, 1.0, 1.0,
+            0.0, 0.0, 1.0,
+        ]);
+
+        let warped = warp(&image, projection, Interpolation::Nearest)
+            .expect("projection is invertible");
+        assert_pixels_eq!(warped, expected);
+    }
+
+    fn assert_points_close(p: Point2, q: Point2) {
+        assert!((p.x - q.x).abs() < 1e-3 && (p.y - q.y).abs() < 1e-3, "{:?} != {:?}", p, q);
+    }
+
+    #[test]
+    fn test_affine2_builders() {
+        assert_points_close(Affine2::identity() * Point2::new(3f32, 4f32), Point2::new(3f32, 4f32));
+        assert_points_close(Affine2::translate(1f32, 2f32) * Point2::new(3f32, 4f32), Point2::new(4f32, 6f32));
+        assert_points_close(Affine2::scale(2f32, 3f32) * Point2::new(3f32, 4f32), Point2::new(6f32, 12f32));
+        assert_points_close(Affine2::shear(1f32, 0f32) * Point2::new(3f32, 4f32), Point2::new(7f32, 4f32));
+
+        use std::f32;
+        let rotated = Affine2::rotate(f32::consts::PI / 2f32) * Point2::new(1f32, 0f32);
+        assert_points_close(rotated, Point2::new(0f32, 1f32));
+    }
+
+    #[test]
+    fn test_affine2_composition() {
+        // Rotating a point a quarter turn clockwise about (1, 1) should map
+        // (2, 1) (one unit to the right of the center) to (1, 2) (one unit
+        // below the center).
+        use std::f32;
+        let about_center = Affine2::translate(1f32, 1f32)
+            * Affine2::rotate(f32::consts::PI / 2f32)
+            * Affine2::translate(-1f32, -1f32);
+
+        assert_points_close(about_center * Point2::new(2f32, 1f32), Point2::new(1f32, 2f32));
+    }
+
+    #[test]
+    fn test_affine2_from_control_points() {
+        let t = Affine2::from_control_points(
+            [(0f32, 0f32), (1f32, 0f32), (0f32, 1f32)],
+            [(1f32, 1f32), (3f32, 1f32), (1f32, 4f32)],
+        ).expect("control points are not collinear");
+
+        assert_points_close(t * Point2::new(0f32, 0f32), Point2::new(1f32, 1f32));
+        assert_points_close(t * Point2::new(1f32, 0f32), Point2::new(3f32, 1f32));
+        assert_points_close(t * Point2::new(0f32, 1f32), Point2::new(1f32, 4f32));
+    }
+
+    #[test]
+    fn test_affine2_from_control_points_collinear() {
+        let t = Affine2::from_control_points(
+            [(0f32, 0f32), (1f32, 0f32), (2f32, 0f32)],
+            [(0f32, 0f32), (1f32, 1f32), (2f32, 2f32)],
+        );
+        assert!(t.is_none());
+    }
+
+    #[test]
+    fn test_projection_from_control_points() {
+        let t = Projection::from_control_points(
+            [(0f32, 0f32), (1f32, 0f32), (1f32, 1f32), (0f32, 1f32)],
+            [(1f32, 1f32), (4f32, 1f32), (4f32, 3f32), (1f32, 3f32)],
+        ).expect("control points are in general position");
+
+        assert_points_close(t * Point2::new(0f32, 0f32), Point2::new(1f32, 1f32));
+        assert_points_close(t * Point2::new(1f32, 0f32), Point2::new(4f32, 1f32));
+        assert_points_close(t * Point2::new(1f32, 1f32), Point2::new(4f32, 3f32));
+        assert_points_close(t * Point2::new(0f32, 1f32), Point2::new(1f32, 3f32));
+    }
+
+    #[test]
+    fn test_projection_from_control_points_degenerate() {
+        let t = Projection::from_control_points(
+            [(0f32, 0f32), (1f32, 0f32), (2f32, 0f32), (3f32, 0f32)],
+            [(0f32, 0f32), (1f32, 1f32), (2f32, 2f32), (3f32, 3f32)],
+        );
+        assert!(t.is_none());
+    }
 }